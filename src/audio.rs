@@ -0,0 +1,72 @@
+//! Optional audio-reactive input, inspired by the nannou `audio-flow` example.
+//!
+//! When compiled with `--features audio`, [`AudioInput::start`] opens the
+//! default input device on a background thread and keeps an exponentially
+//! smoothed RMS amplitude around for the main loop to sample every frame.
+//! Without the feature (or without an audio device available), callers just
+//! get a stub that always reports zero amplitude, so the rest of the crate
+//! never needs to special-case "no audio".
+
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::Arc;
+
+#[cfg(feature = "audio")]
+use cpal::traits::{DeviceTrait, HostTrait, StreamTrait};
+
+/// Shared handle to the live-updated amplitude, safe to read from any thread.
+pub struct AudioInput {
+    amplitude: Arc<AtomicU32>,
+    #[cfg(feature = "audio")]
+    _stream: cpal::Stream,
+}
+
+impl AudioInput {
+    /// Opens the default input device and starts streaming samples into an
+    /// exponential moving average of the RMS amplitude. Returns `None` if no
+    /// input device is available, or if the `audio` feature is disabled.
+    pub fn start() -> Option<Self> {
+        #[cfg(feature = "audio")]
+        {
+            let host = cpal::default_host();
+            let device = host.default_input_device()?;
+            let config = device.default_input_config().ok()?;
+
+            let amplitude = Arc::new(AtomicU32::new(0f32.to_bits()));
+            let amplitude_cb = Arc::clone(&amplitude);
+
+            let err_fn = |err| eprintln!("audio input error: {err}");
+            let stream = device
+                .build_input_stream(
+                    &config.into(),
+                    move |data: &[f32], _| {
+                        let mean_sq: f32 =
+                            data.iter().map(|s| s * s).sum::<f32>() / data.len().max(1) as f32;
+                        let rms = mean_sq.sqrt();
+
+                        let prev = f32::from_bits(amplitude_cb.load(Ordering::Relaxed));
+                        let smoothed = 0.9 * prev + 0.1 * rms;
+                        amplitude_cb.store(smoothed.to_bits(), Ordering::Relaxed);
+                    },
+                    err_fn,
+                    None,
+                )
+                .ok()?;
+            stream.play().ok()?;
+
+            Some(Self {
+                amplitude,
+                _stream: stream,
+            })
+        }
+
+        #[cfg(not(feature = "audio"))]
+        {
+            None
+        }
+    }
+
+    /// Current smoothed amplitude, roughly in `0.0..1.0` for normal input levels.
+    pub fn amplitude(&self) -> f32 {
+        f32::from_bits(self.amplitude.load(Ordering::Relaxed))
+    }
+}
@@ -0,0 +1,118 @@
+//! Particle emitters: spawn shapes, lifetimes, and color-over-life
+//! gradients, in the spirit of macroquad's `particles` crate.
+//!
+//! Each [`Emitter`] also carries its own `noise_offset` so several emitters
+//! can share one flow field while reading a different patch of it, making
+//! them look like distinct sources instead of clones of each other.
+
+use std::ops::RangeInclusive;
+
+use pastel::Color;
+use rand::prelude::*;
+
+use crate::Coord;
+
+/// Where a newly spawned particle is placed.
+#[derive(Debug, Clone, Copy)]
+pub enum SpawnShape {
+    /// No built-in emitter uses this yet; kept for a future single-point source.
+    #[allow(dead_code)]
+    Point(Coord),
+    Rect { center: Coord, half_extent: Coord },
+    Circle { center: Coord, radius: f32 },
+}
+
+impl SpawnShape {
+    fn sample(&self) -> Coord {
+        match *self {
+            SpawnShape::Point(coord) => coord,
+            SpawnShape::Rect {
+                center,
+                half_extent,
+            } => crate::rng::with(|rng| {
+                Coord::new(
+                    center.x + rng.random_range(-half_extent.x..=half_extent.x),
+                    center.y + rng.random_range(-half_extent.y..=half_extent.y),
+                )
+            }),
+            SpawnShape::Circle { center, radius } => crate::rng::with(|rng| {
+                let angle: f32 = rng.random_range(0.0..std::f32::consts::TAU);
+                let r = radius * rng.random_range(0.0f32..1.0).sqrt();
+                Coord::new(center.x + angle.cos() * r, center.y + angle.sin() * r)
+            }),
+        }
+    }
+}
+
+/// A color keyframe at a normalized age (`0.0..=1.0`).
+#[derive(Debug, Clone, Copy)]
+pub struct GradientStop {
+    pub age: f32,
+    pub color: (u8, u8, u8),
+}
+
+/// A color-over-life gradient, sampled by normalized `age / lifetime`.
+#[derive(Debug, Clone)]
+pub struct Gradient {
+    stops: Vec<GradientStop>,
+}
+
+impl Gradient {
+    pub fn new(stops: Vec<GradientStop>) -> Self {
+        assert!(!stops.is_empty(), "a gradient needs at least one stop");
+        Self { stops }
+    }
+
+    pub fn sample(&self, t: f32) -> (u8, u8, u8) {
+        let t = t.clamp(0.0, 1.0);
+        let mut lo = self.stops[0];
+        let mut hi = *self.stops.last().unwrap();
+        for pair in self.stops.windows(2) {
+            if t >= pair[0].age && t <= pair[1].age {
+                lo = pair[0];
+                hi = pair[1];
+                break;
+            }
+        }
+
+        let span = (hi.age - lo.age).max(f32::EPSILON);
+        let local_t = ((t - lo.age) / span).clamp(0.0, 1.0);
+        let lerp = |a: u8, b: u8| (a as f32 + (b as f32 - a as f32) * local_t).round() as u8;
+        (
+            lerp(lo.color.0, hi.color.0),
+            lerp(lo.color.1, hi.color.1),
+            lerp(lo.color.2, hi.color.2),
+        )
+    }
+}
+
+/// A named flow-field source: spawn shape, lifetime range, color-over-life
+/// gradient, and a noise offset so several emitters can coexist on one canvas.
+pub struct Emitter {
+    /// Not displayed anywhere yet; reserved for a per-emitter label in the
+    /// control panel once it grows a UI for switching between emitters.
+    #[allow(dead_code)]
+    pub name: String,
+    pub shape: SpawnShape,
+    pub lifetime: RangeInclusive<f32>,
+    pub gradient: Gradient,
+    pub noise_offset: Coord,
+}
+
+impl Emitter {
+    /// Picks a spawn position and a lifetime for a new particle.
+    pub fn spawn(&self) -> (Coord, f32) {
+        let lifetime = crate::rng::with(|rng| rng.random_range(self.lifetime.clone()));
+        (self.shape.sample(), lifetime)
+    }
+
+    /// Samples the gradient at `age / lifetime`, blended towards white for a
+    /// faster-moving particle (`speed` normalized to roughly `0.0..=1.0`) so
+    /// a fast streak reads hotter than a lingering one at the same age.
+    pub fn colorize(&self, age: f32, lifetime: f32, speed: f32) -> Color {
+        let (r, g, b) = self.gradient.sample(age / lifetime.max(f32::EPSILON));
+        let heat = speed.clamp(0.0, 1.0) * 0.3;
+        let lerp_to_white = |c: u8| (c as f32 + (255.0 - c as f32) * heat).round() as u8;
+        Color::from_rgb(lerp_to_white(r), lerp_to_white(g), lerp_to_white(b))
+    }
+}
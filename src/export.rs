@@ -0,0 +1,33 @@
+//! Headless rendering: writes each frame of the pixel buffer to disk as a
+//! numbered PNG instead of presenting it in a `minifb` window, so a run can
+//! be assembled into a video or diffed frame-by-frame for a visual
+//! regression test.
+
+use std::path::Path;
+
+use image::{ImageBuffer, Rgb};
+
+/// Encodes `buffer` (the same packed `0x00RRGGBB` pixels `minifb` draws) as
+/// `out_dir/frame-NNNNN.png`, creating `out_dir` if it doesn't exist yet.
+pub fn write_frame(
+    buffer: &[u32],
+    width: usize,
+    height: usize,
+    out_dir: &Path,
+    frame_index: u32,
+) -> std::io::Result<()> {
+    std::fs::create_dir_all(out_dir)?;
+
+    let mut image = ImageBuffer::<Rgb<u8>, _>::new(width as u32, height as u32);
+    for (i, pixel) in buffer.iter().enumerate() {
+        let r = ((pixel >> 16) & 0xff) as u8;
+        let g = ((pixel >> 8) & 0xff) as u8;
+        let b = (pixel & 0xff) as u8;
+        image.put_pixel((i % width) as u32, (i / width) as u32, Rgb([r, g, b]));
+    }
+
+    let path = out_dir.join(format!("frame-{frame_index:05}.png"));
+    image
+        .save(path)
+        .map_err(std::io::Error::other)
+}
@@ -1,21 +1,25 @@
-use std::{
-    sync::atomic::{AtomicU32, Ordering},
-    time::{Duration, Instant},
-};
+use std::time::{Duration, Instant};
 
 use minifb::{Window, WindowOptions};
-use noise::{
-    Checkerboard, Fbm, HybridMulti, NoiseFn, OpenSimplex, Perlin, PerlinSurflet, RidgedMulti,
-    Simplex, SuperSimplex, Worley,
-};
+use noise::NoiseFn;
 use pastel::Color;
-use rand::prelude::*;
 use rayon::prelude::*;
 
-trait Noise2D: NoiseFn<f64, 2> {}
-impl<T> Noise2D for T where T: NoiseFn<f64, 2> {}
-
-trait Noise3D: NoiseFn<f64, 3> {}
+mod audio;
+mod emitter;
+mod export;
+mod noise_kind;
+mod param_map;
+mod rng;
+mod ui;
+
+use audio::AudioInput;
+use emitter::{Emitter, Gradient, GradientStop, SpawnShape};
+use noise_kind::NoiseKind;
+use param_map::Config;
+use ui::ControlPanel;
+
+pub(crate) trait Noise3D: NoiseFn<f64, 3> {}
 impl<T> Noise3D for T where T: NoiseFn<f64, 3> {}
 
 /// A coordinate in the [-1:1] space
@@ -32,40 +36,98 @@ impl Coord {
             y: y.try_into().map_err(|_| ()).unwrap(),
         }
     }
-    pub fn rand() -> Self {
-        let mut rng = rand::thread_rng();
-        let x = rng.gen_range(-1.0..1.0);
-        let y = rng.gen_range(-1.0..1.0);
-        Self::new(x, y)
+}
+
+impl Coord {
+    pub fn zero() -> Self {
+        Self { x: 0., y: 0. }
+    }
+
+    pub fn len(&self) -> f32 {
+        (self.x * self.x + self.y * self.y).sqrt()
+    }
+}
+
+impl std::ops::AddAssign for Coord {
+    fn add_assign(&mut self, rhs: Self) {
+        self.x += rhs.x;
+        self.y += rhs.y;
+    }
+}
+
+impl std::ops::MulAssign<f32> for Coord {
+    fn mul_assign(&mut self, rhs: f32) {
+        self.x *= rhs;
+        self.y *= rhs;
     }
 }
 
 #[derive(Debug, Clone)]
 struct Particle {
     coord: Coord,
+    vel: Coord,
+    /// Index into `emitters`: which emitter spawned (and will respawn) this particle.
+    emitter: usize,
+    age: f32,
+    lifetime: f32,
 }
 
 impl Particle {
-    pub fn new() -> Self {
+    pub fn new(emitter: usize, emitters: &[Emitter]) -> Self {
+        let (coord, lifetime) = emitters[emitter].spawn();
         Self {
-            coord: Coord::rand(),
+            coord,
+            vel: Coord::zero(),
+            emitter,
+            age: 0.0,
+            lifetime,
         }
     }
 
-    pub fn update<Noise: Noise3D>(&mut self, param: &Param<Noise>) {
-        let direction = param.noise_get(self.coord.x, self.coord.y) * 180.;
+    /// Recycles this particle through its emitter, as if newly spawned.
+    fn respawn(&mut self, emitters: &[Emitter]) {
+        let (coord, lifetime) = emitters[self.emitter].spawn();
+        self.coord = coord;
+        self.vel = Coord::zero();
+        self.age = 0.0;
+        self.lifetime = lifetime;
+    }
+
+    pub fn update(&mut self, param: &Param, emitters: &[Emitter]) {
+        self.age += 1.0;
+
+        // Sample the flow field and turn the noise value into a steering
+        // direction, exactly like before, offset per-emitter so several
+        // emitters don't read the same patch of the field.
+        let offset = emitters[self.emitter].noise_offset;
+        let direction =
+            param.noise_get(self.coord.x + offset.x, self.coord.y + offset.y) * 180.;
         let direction = direction.to_radians() as f32;
-        self.coord.x += direction.cos() / 1000.;
-        self.coord.y += direction.sin() / 1000.;
+        let steer = Coord::new(direction.cos(), direction.sin());
+        let surge = 1.0 + param.amplitude * param.audio_gain;
+
+        self.vel += Coord::new(
+            steer.x * param.accel * surge,
+            steer.y * param.accel * surge,
+        );
+        if self.vel.len() > param.max_speed {
+            let scale = param.max_speed / self.vel.len();
+            self.vel *= scale;
+        }
+        self.vel *= param.damping;
+
+        self.coord += self.vel;
 
-        // The particle escaped the canvas
-        // We should re-insert it into the canvas
-        if !(-1.0..=1.0).contains(&self.coord.x) || !(-1.0..=1.0).contains(&self.coord.y) {
-            self.coord = Coord::rand();
+        // The particle escaped the canvas, or lived out its lifetime: recycle
+        // it through its emitter.
+        let escaped =
+            !(-1.0..=1.0).contains(&self.coord.x) || !(-1.0..=1.0).contains(&self.coord.y);
+        if escaped || self.age >= self.lifetime {
+            self.respawn(emitters);
         }
     }
 
-    pub fn to_coord<Noise>(&self, param: &Param<Noise>) -> usize {
+    pub fn to_coord(&self, param: &Param) -> usize {
         // range [0:2]
         let x = self.coord.x + 1.0;
         let y = self.coord.y + 1.0;
@@ -78,32 +140,75 @@ impl Particle {
         x as usize + param.width * y as usize
     }
 
-    pub fn colorize<Noise>(&self, param: &Param<Noise>) -> Color {
-        Color::red()
-        // pastel::HSLA {
-        //     h: 360.,
-        //     s: 1.0,
-        //     l: 1.0,
-        //     alpha: 0.,
-        // }
+    pub fn colorize(&self, param: &Param, emitters: &[Emitter]) -> Color {
+        let speed = self.vel.len() / param.max_speed.max(f32::EPSILON);
+        emitters[self.emitter].colorize(self.age, self.lifetime, speed)
     }
 }
 
-struct Param<Noise> {
-    noise: Noise,
+struct Param {
+    noise: Box<dyn Noise3D>,
     iteration_speed: u8,
     iteration: u16,
     width: usize,
     height: usize,
+    /// How strongly the steering vector pulls on a particle's velocity each tick.
+    accel: f32,
+    /// Fraction of velocity kept every tick; lower values settle faster.
+    damping: f32,
+    /// Hard cap on `vel`'s magnitude so particles can't run away.
+    max_speed: f32,
+    /// Smoothed microphone RMS amplitude for this frame, in roughly `0.0..1.0`.
+    /// Stays `0.0` when no audio input is available.
+    amplitude: f32,
+    /// How strongly `amplitude` scales steering and the `iteration` advance.
+    audio_gain: f32,
+    /// Multiplier applied to the per-frame hue-rotation trail fade.
+    trail_fade: f32,
 }
 
-impl<Noise: Noise3D> Param<Noise> {
+impl Param {
     pub fn noise_get(&self, x: f32, y: f32) -> f64 {
         // we must bring back self.iteration in the range [-1:1]
         // now it's in the range [0:1]
         let iteration = self.iteration as f64 / u16::MAX as f64;
         self.noise.get([x as f64, y as f64, iteration * 2. - 1.])
     }
+
+    /// Rebuilds `self.noise` in place from the given kind/seed/tuning, so the
+    /// control panel can switch generators without restarting the binary.
+    #[allow(clippy::too_many_arguments)]
+    pub fn rebuild_noise(
+        &mut self,
+        kind: NoiseKind,
+        seed: u32,
+        octaves: usize,
+        frequency: f64,
+        lacunarity: f64,
+        persistence: f64,
+        worley_cell_size: f64,
+    ) {
+        self.noise = kind.build(seed, octaves, frequency, lacunarity, persistence, worley_cell_size);
+    }
+}
+
+/// Advances every particle by one tick and paints the trail + particles into
+/// `buffer`. Shared by the windowed and headless (`--frames`/`--out`) modes.
+fn advance_frame(buffer: &mut [u32], particles: &mut [Particle], param: &Param, emitters: &[Emitter]) {
+    // Make a funny trail. Captured by value (not `&Param`) so this stays
+    // parallel regardless of whether the active noise generator is `Sync`.
+    let trail_fade = param.trail_fade as f64;
+    buffer.par_iter_mut().for_each(|buf| {
+        let color = u32_to_color(*buf);
+        *buf = color.rotate_hue(trail_fade).to_u32();
+    });
+
+    // Not parallelized: some generators (Worley, via `noise`'s internal `Rc`)
+    // aren't `Sync`, so `Param` as a whole can't be shared across threads here.
+    for particle in particles.iter_mut() {
+        particle.update(param, emitters);
+        buffer[particle.to_coord(param)] = particle.colorize(param, emitters).to_u32();
+    }
 }
 
 fn main() {
@@ -113,62 +218,145 @@ fn main() {
     let nb_particles = 200_000;
     // let nb_particles = 1;
 
-    let mut buffer = vec![0; width * height];
-
-    let mut window = Window::new("Perlin", width, height, WindowOptions::default()).unwrap();
+    let config = Config::from_args(std::env::args()).unwrap_or_else(|err| {
+        eprintln!("error: {err}");
+        std::process::exit(1);
+    });
+    // Only the headless `--frames`/`--out` export needs reproducible particle
+    // placement; leave interactive runs on OS entropy like before.
+    if config.frames.is_some() {
+        rng::seed(config.seed as u64);
+    }
 
+    let mut buffer = vec![0; width * height];
     let mut particles = Vec::with_capacity(width * height);
 
-    // let noise = Perlin::new(14);
-    // let noise = PerlinSurflet::new(14);
-    // let noise = Checkerboard::new(14).set_size(32);
-    // let noise = HybridMulti::<Perlin>::new(14);
-    // let noise = OpenSimplex::new(14);
-    // let noise = RidgedMulti::<Perlin>::new(14);
-    // let noise = Simplex::new(14);
-    // let noise = SuperSimplex::new(14);
-    // let noise = Worley::new(14); // can't parallelize with this one
-    let noise = Fbm::<Perlin>::new(14);
+    let mut control_panel = ControlPanel::with_controls(ui::Controls {
+        noise_kind: config.noise_kind,
+        seed: config.seed,
+        octaves: config.octaves,
+        frequency: config.frequency,
+        lacunarity: config.lacunarity,
+        persistence: config.persistence,
+        worley_cell_size: config.worley_cell_size,
+        ..ui::Controls::default()
+    });
+    let controls = &control_panel.controls;
+    let noise = controls.noise_kind.build(
+        controls.seed,
+        controls.octaves,
+        controls.frequency,
+        controls.lacunarity,
+        controls.persistence,
+        controls.worley_cell_size,
+    );
 
     let mut param = Param {
         noise,
-        iteration_speed: 5,
+        iteration_speed: controls.iteration_speed,
         iteration: 0,
         width,
         height,
+        accel: 0.02,
+        damping: 0.95,
+        max_speed: 0.01,
+        amplitude: 0.0,
+        audio_gain: 4.0,
+        trail_fade: controls.trail_fade,
     };
 
-    for _ in 0..nb_particles {
-        let mut particle = Particle::new();
-        particle.update(&param);
+    // Absent the `audio` feature, or without an input device, this is `None`
+    // and `param.amplitude` just stays at `0.0` forever.
+    let audio_input = AudioInput::start();
+
+    let emitters = vec![
+        Emitter {
+            name: "warm".to_string(),
+            shape: SpawnShape::Rect {
+                center: Coord::zero(),
+                half_extent: Coord::new(1.0, 1.0),
+            },
+            lifetime: 120.0..=400.0,
+            gradient: Gradient::new(vec![
+                GradientStop {
+                    age: 0.0,
+                    color: (255, 200, 40),
+                },
+                GradientStop {
+                    age: 1.0,
+                    color: (120, 20, 0),
+                },
+            ]),
+            noise_offset: Coord::zero(),
+        },
+        Emitter {
+            name: "cool".to_string(),
+            shape: SpawnShape::Circle {
+                center: Coord::zero(),
+                radius: 0.6,
+            },
+            lifetime: 120.0..=400.0,
+            gradient: Gradient::new(vec![
+                GradientStop {
+                    age: 0.0,
+                    color: (80, 200, 255),
+                },
+                GradientStop {
+                    age: 1.0,
+                    color: (10, 20, 90),
+                },
+            ]),
+            noise_offset: Coord::new(5.0, 5.0),
+        },
+    ];
+
+    for i in 0..nb_particles {
+        let mut particle = Particle::new(i % emitters.len(), &emitters);
+        particle.update(&param, &emitters);
         particles.push(particle);
     }
 
-    loop {
-        param.iteration += 1;
-        let now = Instant::now();
+    if let (Some(frames), Some(out_dir)) = (config.frames, &config.out_dir) {
+        run_headless(frames, out_dir, &mut param, &emitters, &mut particles, &mut buffer);
+        return;
+    }
 
-        // Make a funny trail
-        buffer.par_iter_mut().for_each(|buf| {
-            let color = u32_to_color(*buf);
-            *buf = color.rotate_hue(1.).to_u32();
-        });
+    let mut window = Window::new("Perlin", width, height, WindowOptions::default()).unwrap();
 
-        // reset the buffer to black entirely
-        // buffer.fill(0);
+    loop {
+        if let Some(audio_input) = &audio_input {
+            param.amplitude = audio_input.amplitude();
+        }
+        let iteration_step = param.iteration_speed as f32 * (1.0 + param.amplitude * param.audio_gain);
+        param.iteration = param.iteration.saturating_add(iteration_step as u16);
+        let now = Instant::now();
 
-        let shared_buffer: &[AtomicU32] = unsafe { std::mem::transmute(buffer.as_slice()) };
+        advance_frame(&mut buffer, &mut particles, &param, &emitters);
 
-        // update and insert all the particle in the buffer
-        particles.par_iter_mut().for_each(|particle| {
-            particle.update(&param);
+        // dbg!(&particles[0]);
 
-            shared_buffer[particle.to_coord(&param)]
-                .store(particle.colorize(&param).to_u32(), Ordering::Relaxed);
+        control_panel.update(&window, &mut buffer, width, height);
+        let controls = &control_panel.controls;
+        if controls.noise_dirty {
+            param.rebuild_noise(
+                controls.noise_kind,
+                controls.seed,
+                controls.octaves,
+                controls.frequency,
+                controls.lacunarity,
+                controls.persistence,
+                controls.worley_cell_size,
+            );
+        }
+        param.iteration_speed = controls.iteration_speed;
+        param.trail_fade = controls.trail_fade;
+        let mut next_emitter = particles.len() % emitters.len();
+        particles.resize_with(controls.particle_count, || {
+            let particle = Particle::new(next_emitter, &emitters);
+            next_emitter = (next_emitter + 1) % emitters.len();
+            particle
         });
 
-        // dbg!(&particles[0]);
-
         window.update_with_buffer(&buffer, width, height).unwrap();
 
         let elapsed = now.elapsed();
@@ -180,6 +368,29 @@ fn main() {
     }
 }
 
+/// Runs the simulation without opening a `minifb` window, writing each frame
+/// to `out_dir` as a numbered PNG.
+fn run_headless(
+    frames: u32,
+    out_dir: &std::path::Path,
+    param: &mut Param,
+    emitters: &[Emitter],
+    particles: &mut [Particle],
+    buffer: &mut [u32],
+) {
+    for frame_index in 0..frames {
+        param.iteration = param.iteration.saturating_add(param.iteration_speed as u16);
+        advance_frame(buffer, particles, param, emitters);
+
+        if let Err(err) = export::write_frame(buffer, param.width, param.height, out_dir, frame_index) {
+            eprintln!("error: failed to write frame {frame_index}: {err}");
+            std::process::exit(1);
+        }
+    }
+
+    println!("wrote {frames} frame(s) to {}", out_dir.display());
+}
+
 pub fn u32_to_color(n: u32) -> Color {
     let r = (n >> 16) & 0xff;
     let g = (n >> 8) & 0xff;
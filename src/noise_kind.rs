@@ -0,0 +1,111 @@
+//! The set of `noise` crate generators the control panel can switch between
+//! at runtime, boxed behind [`Noise3D`] so swapping kinds never needs a
+//! recompile.
+
+use noise::{
+    Checkerboard, Fbm, HybridMulti, MultiFractal, OpenSimplex, Perlin, PerlinSurflet, RidgedMulti,
+    Simplex, SuperSimplex, Worley,
+};
+
+use crate::Noise3D;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum NoiseKind {
+    Perlin,
+    PerlinSurflet,
+    Checkerboard,
+    Fbm,
+    HybridMulti,
+    OpenSimplex,
+    RidgedMulti,
+    Simplex,
+    SuperSimplex,
+    Worley,
+}
+
+impl NoiseKind {
+    pub const ALL: &'static [NoiseKind] = &[
+        NoiseKind::Perlin,
+        NoiseKind::PerlinSurflet,
+        NoiseKind::Checkerboard,
+        NoiseKind::Fbm,
+        NoiseKind::HybridMulti,
+        NoiseKind::OpenSimplex,
+        NoiseKind::RidgedMulti,
+        NoiseKind::Simplex,
+        NoiseKind::SuperSimplex,
+        NoiseKind::Worley,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            NoiseKind::Perlin => "perlin",
+            NoiseKind::PerlinSurflet => "perlin surflet",
+            NoiseKind::Checkerboard => "checkerboard",
+            NoiseKind::Fbm => "fbm",
+            NoiseKind::HybridMulti => "hybrid multi",
+            NoiseKind::OpenSimplex => "open simplex",
+            NoiseKind::RidgedMulti => "ridged multi",
+            NoiseKind::Simplex => "simplex",
+            NoiseKind::SuperSimplex => "super simplex",
+            NoiseKind::Worley => "worley", // can't parallelize with this one
+        }
+    }
+
+    /// Whether `octaves`/`lacunarity`/`persistence` apply to this kind.
+    pub fn supports_octaves(&self) -> bool {
+        matches!(
+            self,
+            NoiseKind::Fbm | NoiseKind::HybridMulti | NoiseKind::RidgedMulti
+        )
+    }
+
+    /// Builds the boxed generator for this kind, seeded and tuned from
+    /// `seed`/`octaves`/`frequency`/`lacunarity`/`persistence`/`worley_cell_size`.
+    pub fn build(
+        &self,
+        seed: u32,
+        octaves: usize,
+        frequency: f64,
+        lacunarity: f64,
+        persistence: f64,
+        worley_cell_size: f64,
+    ) -> Box<dyn Noise3D> {
+        match self {
+            NoiseKind::Perlin => Box::new(Perlin::new(seed)),
+            NoiseKind::PerlinSurflet => Box::new(PerlinSurflet::new(seed)),
+            // Checkerboard is deterministic (no seed), so `seed` is unused here.
+            NoiseKind::Checkerboard => Box::new(Checkerboard::default()),
+            NoiseKind::Fbm => Box::new(
+                Fbm::<Perlin>::new(seed)
+                    .set_octaves(octaves)
+                    .set_frequency(frequency)
+                    .set_lacunarity(lacunarity)
+                    .set_persistence(persistence),
+            ),
+            NoiseKind::HybridMulti => Box::new(
+                HybridMulti::<Perlin>::new(seed)
+                    .set_octaves(octaves)
+                    .set_frequency(frequency)
+                    .set_lacunarity(lacunarity)
+                    .set_persistence(persistence),
+            ),
+            NoiseKind::OpenSimplex => Box::new(OpenSimplex::new(seed)),
+            NoiseKind::RidgedMulti => Box::new(
+                RidgedMulti::<Perlin>::new(seed)
+                    .set_octaves(octaves)
+                    .set_frequency(frequency)
+                    .set_lacunarity(lacunarity)
+                    .set_persistence(persistence),
+            ),
+            NoiseKind::Simplex => Box::new(Simplex::new(seed)),
+            NoiseKind::SuperSimplex => Box::new(SuperSimplex::new(seed)),
+            // `worley_cell_size` is a distance, inversely related to `Worley`'s
+            // own `frequency` knob (higher frequency = smaller cells), so invert it here.
+            NoiseKind::Worley => {
+                let frequency = if worley_cell_size == 0.0 { f64::MAX } else { 1.0 / worley_cell_size };
+                Box::new(Worley::new(seed).set_frequency(frequency))
+            }
+        }
+    }
+}
@@ -0,0 +1,275 @@
+//! CLI-driven configuration for the starting noise generator.
+//!
+//! Mirrors the `noise <kind> <seed> <arg> ...` command style from the
+//! scritcher noise subsystem: the first two positional arguments pick the
+//! generator and its seed, and every `--key value` pair after that tunes it.
+//! Building the actual `NoiseFn` from this is [`NoiseKind::build`], so this
+//! module only has to parse and validate the table.
+
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+use crate::noise_kind::NoiseKind;
+
+/// A flat string->value table, the parsed form of the CLI's `--key value` pairs.
+#[derive(Debug, Default)]
+struct ParamMap {
+    values: HashMap<String, String>,
+}
+
+impl ParamMap {
+    fn get(&self, key: &str) -> Option<&str> {
+        self.values.get(key).map(String::as_str)
+    }
+
+    fn get_f64(&self, key: &str) -> Result<Option<f64>, String> {
+        self.get(key)
+            .map(|v| {
+                v.parse::<f64>()
+                    .map_err(|_| format!("`--{key}` must be a number, got `{v}`"))
+            })
+            .transpose()
+    }
+
+    fn get_usize(&self, key: &str) -> Result<Option<usize>, String> {
+        self.get(key)
+            .map(|v| {
+                v.parse::<usize>()
+                    .map_err(|_| format!("`--{key}` must be a non-negative integer, got `{v}`"))
+            })
+            .transpose()
+    }
+
+    fn get_u32(&self, key: &str) -> Result<Option<u32>, String> {
+        self.get(key)
+            .map(|v| {
+                v.parse::<u32>()
+                    .map_err(|_| format!("`--{key}` must be a non-negative integer, got `{v}`"))
+            })
+            .transpose()
+    }
+}
+
+/// The starting noise generator and its tuning, parsed from the process args.
+pub struct Config {
+    pub noise_kind: NoiseKind,
+    pub seed: u32,
+    pub octaves: usize,
+    pub frequency: f64,
+    pub lacunarity: f64,
+    pub persistence: f64,
+    pub worley_cell_size: f64,
+    /// Headless export mode: render `frames` frames to `out_dir` as numbered
+    /// PNGs instead of opening a `minifb` window. Either both are set, or
+    /// neither is and the simulation runs in its usual windowed mode.
+    pub frames: Option<u32>,
+    pub out_dir: Option<PathBuf>,
+}
+
+impl Config {
+    pub const DEFAULT_SEED: u32 = 14;
+    pub const DEFAULT_OCTAVES: usize = 6;
+    pub const DEFAULT_FREQUENCY: f64 = 1.0;
+    pub const DEFAULT_LACUNARITY: f64 = std::f64::consts::PI * 2.0 / 3.0;
+    pub const DEFAULT_PERSISTENCE: f64 = 0.5;
+    pub const DEFAULT_WORLEY_CELL_SIZE: f64 = 1.0;
+
+    /// Parses `noise <kind> [seed] [--octaves N] [--frequency F] [--lacunarity F]
+    /// [--persistence F] [--worley-cell-size F] [--seed N] [--frames N] [--out DIR]`.
+    ///
+    /// Tuning knobs that don't apply to the chosen kind (e.g. `--octaves` on
+    /// `perlin`) are rejected rather than silently ignored. `--seed`, if
+    /// given, overrides the positional seed (see [`crate::rng::seed`]).
+    pub fn from_args(args: impl Iterator<Item = String>) -> Result<Self, String> {
+        let mut positional = Vec::new();
+        let mut map = ParamMap::default();
+
+        let mut args = args.skip(1).peekable();
+        while let Some(arg) = args.next() {
+            match arg.strip_prefix("--") {
+                Some(key) => {
+                    let value = args
+                        .next()
+                        .ok_or_else(|| format!("missing value for `--{key}`"))?;
+                    map.values.insert(key.to_string(), value);
+                }
+                None => positional.push(arg),
+            }
+        }
+
+        let noise_kind = match positional.first() {
+            Some(kind) => parse_noise_kind(kind)?,
+            None => NoiseKind::Fbm,
+        };
+        let positional_seed = match positional.get(1) {
+            Some(seed) => Some(
+                seed.parse::<u32>()
+                    .map_err(|_| format!("seed must be a non-negative integer, got `{seed}`"))?,
+            ),
+            None => None,
+        };
+        let seed = match map.get_u32("seed")? {
+            Some(seed) => seed,
+            None => positional_seed.unwrap_or(Self::DEFAULT_SEED),
+        };
+
+        let octaves = map.get_usize("octaves")?;
+        let frequency = map.get_f64("frequency")?;
+        let lacunarity = map.get_f64("lacunarity")?;
+        let persistence = map.get_f64("persistence")?;
+        let worley_cell_size = map.get_f64("worley-cell-size")?;
+        let frames = map.get_u32("frames")?;
+        let out_dir = map.get("out").map(PathBuf::from);
+
+        if octaves.is_some() && !noise_kind.supports_octaves() {
+            return Err(format!(
+                "`--octaves` has no effect on `{}`, which isn't a fractal noise",
+                noise_kind.label()
+            ));
+        }
+        if lacunarity.is_some() && !noise_kind.supports_octaves() {
+            return Err(format!(
+                "`--lacunarity` has no effect on `{}`, which isn't a fractal noise",
+                noise_kind.label()
+            ));
+        }
+        if persistence.is_some() && !noise_kind.supports_octaves() {
+            return Err(format!(
+                "`--persistence` has no effect on `{}`, which isn't a fractal noise",
+                noise_kind.label()
+            ));
+        }
+        if worley_cell_size.is_some() && noise_kind != NoiseKind::Worley {
+            return Err(format!(
+                "`--worley-cell-size` only applies to `worley`, not `{}`",
+                noise_kind.label()
+            ));
+        }
+        if frames.is_some() != out_dir.is_some() {
+            return Err("`--frames` and `--out` must be passed together".to_string());
+        }
+
+        Ok(Self {
+            noise_kind,
+            seed,
+            octaves: octaves.unwrap_or(Self::DEFAULT_OCTAVES),
+            frequency: frequency.unwrap_or(Self::DEFAULT_FREQUENCY),
+            lacunarity: lacunarity.unwrap_or(Self::DEFAULT_LACUNARITY),
+            persistence: persistence.unwrap_or(Self::DEFAULT_PERSISTENCE),
+            worley_cell_size: worley_cell_size.unwrap_or(Self::DEFAULT_WORLEY_CELL_SIZE),
+            frames,
+            out_dir,
+        })
+    }
+}
+
+fn parse_noise_kind(name: &str) -> Result<NoiseKind, String> {
+    let normalized = name.to_lowercase().replace(['-', '_'], "");
+    NoiseKind::ALL
+        .iter()
+        .copied()
+        .find(|kind| kind.label().replace(' ', "") == normalized)
+        .ok_or_else(|| {
+            let choices = NoiseKind::ALL
+                .iter()
+                .map(|kind| kind.label())
+                .collect::<Vec<_>>()
+                .join(", ");
+            format!("unknown noise kind `{name}`, expected one of: {choices}")
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(s: &str) -> impl Iterator<Item = String> {
+        std::iter::once("noise_for_fun".to_string())
+            .chain(s.split_whitespace().map(String::from).collect::<Vec<_>>())
+    }
+
+    #[test]
+    fn octaves_rejected_on_non_fractal_kind() {
+        let err = Config::from_args(args("perlin --octaves 4")).err().unwrap();
+        assert!(err.contains("--octaves"), "unexpected error: {err}");
+    }
+
+    #[test]
+    fn octaves_accepted_on_fractal_kind() {
+        let config = Config::from_args(args("fbm --octaves 4")).unwrap();
+        assert_eq!(config.octaves, 4);
+    }
+
+    #[test]
+    fn lacunarity_rejected_on_non_fractal_kind() {
+        let err = Config::from_args(args("perlin --lacunarity 2.5")).err().unwrap();
+        assert!(err.contains("--lacunarity"), "unexpected error: {err}");
+    }
+
+    #[test]
+    fn lacunarity_accepted_on_fractal_kind() {
+        let config = Config::from_args(args("fbm --lacunarity 2.5")).unwrap();
+        assert_eq!(config.lacunarity, 2.5);
+    }
+
+    #[test]
+    fn persistence_rejected_on_non_fractal_kind() {
+        let err = Config::from_args(args("perlin --persistence 0.3")).err().unwrap();
+        assert!(err.contains("--persistence"), "unexpected error: {err}");
+    }
+
+    #[test]
+    fn persistence_accepted_on_fractal_kind() {
+        let config = Config::from_args(args("fbm --persistence 0.3")).unwrap();
+        assert_eq!(config.persistence, 0.3);
+    }
+
+    #[test]
+    fn worley_cell_size_rejected_on_non_worley_kind() {
+        let err = Config::from_args(args("perlin --worley-cell-size 2.0")).err().unwrap();
+        assert!(err.contains("--worley-cell-size"), "unexpected error: {err}");
+    }
+
+    #[test]
+    fn worley_cell_size_accepted_on_worley_kind() {
+        let config = Config::from_args(args("worley --worley-cell-size 2.0")).unwrap();
+        assert_eq!(config.worley_cell_size, 2.0);
+    }
+
+    #[test]
+    fn frames_without_out_dir_is_rejected() {
+        let err = Config::from_args(args("perlin --frames 10")).err().unwrap();
+        assert!(err.contains("--frames") && err.contains("--out"), "unexpected error: {err}");
+    }
+
+    #[test]
+    fn out_dir_without_frames_is_rejected() {
+        let err = Config::from_args(args("perlin --out /tmp/frames")).err().unwrap();
+        assert!(err.contains("--frames") && err.contains("--out"), "unexpected error: {err}");
+    }
+
+    #[test]
+    fn frames_and_out_dir_together_are_accepted() {
+        let config = Config::from_args(args("perlin --frames 10 --out /tmp/frames")).unwrap();
+        assert_eq!(config.frames, Some(10));
+        assert_eq!(config.out_dir, Some(PathBuf::from("/tmp/frames")));
+    }
+
+    #[test]
+    fn seed_flag_overrides_positional_seed() {
+        let config = Config::from_args(args("perlin 5 --seed 99")).unwrap();
+        assert_eq!(config.seed, 99);
+    }
+
+    #[test]
+    fn positional_seed_is_used_when_no_seed_flag() {
+        let config = Config::from_args(args("perlin 5")).unwrap();
+        assert_eq!(config.seed, 5);
+    }
+
+    #[test]
+    fn unknown_noise_kind_is_rejected() {
+        let err = Config::from_args(args("not-a-kind")).err().unwrap();
+        assert!(err.contains("unknown noise kind"), "unexpected error: {err}");
+    }
+}
@@ -0,0 +1,27 @@
+//! A single global RNG shared by everything that used to reach for
+//! `rand::thread_rng()` directly (emitter spawn sampling, ...).
+//!
+//! `main` only calls [`seed`] for the headless `--frames`/`--out` export, so
+//! that a given `--seed` reproduces an identical frame sequence; interactive
+//! runs never call it and [`with`] falls back to OS entropy, unchanged from
+//! before this RNG was centralized.
+
+use std::sync::{Mutex, OnceLock};
+
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+
+static RNG: OnceLock<Mutex<StdRng>> = OnceLock::new();
+
+/// Seeds the shared RNG. Must be called before anything draws from it.
+pub fn seed(seed: u64) {
+    RNG.set(Mutex::new(StdRng::seed_from_u64(seed)))
+        .unwrap_or_else(|_| panic!("rng::seed was called more than once"));
+}
+
+/// Draws from the shared RNG, lazily seeding it from OS entropy if `seed`
+/// was never called.
+pub fn with<R>(f: impl FnOnce(&mut StdRng) -> R) -> R {
+    let mutex = RNG.get_or_init(|| Mutex::new(StdRng::from_rng(&mut rand::rng())));
+    f(&mut mutex.lock().unwrap())
+}
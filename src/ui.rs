@@ -0,0 +1,347 @@
+//! Live egui control panel, so tuning the simulation no longer means editing
+//! `main` and recompiling.
+//!
+//! We stay on `minifb`'s software buffer instead of migrating to winit+wgpu:
+//! egui runs purely in "give me input, give me back triangles" mode, and we
+//! rasterize its output directly into the same `u32` buffer the particles are
+//! drawn into. `minifb` gives us mouse position/buttons and the keys held
+//! down each frame, which is enough to drive the handful of widgets below.
+
+use std::collections::HashMap;
+use std::time::Instant;
+
+use egui::epaint::{Primitive, Vertex};
+use egui::{Color32, ComboBox, Context, FullOutput, ImageData, Pos2, RawInput, Rect, Slider, TextureId, TexturesDelta};
+use minifb::{MouseButton, MouseMode, Window};
+
+use crate::noise_kind::NoiseKind;
+
+/// Everything the panel lets the user change at runtime.
+pub struct Controls {
+    pub noise_kind: NoiseKind,
+    pub seed: u32,
+    pub octaves: usize,
+    pub frequency: f64,
+    pub lacunarity: f64,
+    pub persistence: f64,
+    pub worley_cell_size: f64,
+    pub iteration_speed: u8,
+    pub trail_fade: f32,
+    pub particle_count: usize,
+    /// Set for one frame whenever a widget changed something that requires
+    /// rebuilding the boxed `NoiseFn` (noise kind, seed, octaves, frequency).
+    pub noise_dirty: bool,
+}
+
+impl Default for Controls {
+    fn default() -> Self {
+        Self {
+            noise_kind: NoiseKind::Fbm,
+            seed: 14,
+            octaves: 6,
+            frequency: 1.0,
+            lacunarity: std::f64::consts::PI * 2.0 / 3.0,
+            persistence: 0.5,
+            worley_cell_size: 1.0,
+            iteration_speed: 5,
+            trail_fade: 1.0,
+            particle_count: 200_000,
+            noise_dirty: false,
+        }
+    }
+}
+
+/// A texture egui has asked us to keep around, decoded to plain RGBA so
+/// [`ControlPanel::paint`] can sample it without touching `ImageData` again.
+struct Texture {
+    size: [usize; 2],
+    pixels: Vec<Color32>,
+}
+
+pub struct ControlPanel {
+    ctx: Context,
+    start: Instant,
+    pub controls: Controls,
+    /// Whether the left mouse button was down last frame, so [`Self::gather_input`]
+    /// can emit the release transition egui needs (it otherwise reads a held
+    /// button as still held forever).
+    mouse_was_down: bool,
+    textures: HashMap<TextureId, Texture>,
+}
+
+impl ControlPanel {
+    /// Convenience constructor for callers happy with [`Controls::default`];
+    /// `main` builds its own `Controls` so it reaches for [`Self::with_controls`] instead.
+    #[allow(dead_code)]
+    pub fn new() -> Self {
+        Self::with_controls(Controls::default())
+    }
+
+    pub fn with_controls(controls: Controls) -> Self {
+        Self {
+            ctx: Context::default(),
+            start: Instant::now(),
+            controls,
+            mouse_was_down: false,
+            textures: HashMap::new(),
+        }
+    }
+
+    /// Runs one egui frame, lets the user fiddle with `self.controls`, and
+    /// rasterizes the resulting UI straight into `buffer`.
+    pub fn update(&mut self, window: &Window, buffer: &mut [u32], width: usize, height: usize) {
+        let raw_input = self.gather_input(window, width, height);
+
+        let controls = &mut self.controls;
+        let full_output = self.ctx.run_ui(raw_input, |top_ui| {
+            egui::Window::new("noise_for_fun").show(top_ui.ctx(), |ui| {
+                let before = (
+                    controls.noise_kind,
+                    controls.seed,
+                    controls.octaves,
+                    controls.frequency,
+                    controls.lacunarity,
+                    controls.persistence,
+                    controls.worley_cell_size,
+                );
+
+                ComboBox::from_label("noise")
+                    .selected_text(controls.noise_kind.label())
+                    .show_ui(ui, |ui| {
+                        for kind in NoiseKind::ALL {
+                            ui.selectable_value(&mut controls.noise_kind, *kind, kind.label());
+                        }
+                    });
+
+                ui.add(Slider::new(&mut controls.seed, 0..=1_000).text("seed"));
+                if controls.noise_kind.supports_octaves() {
+                    ui.add(Slider::new(&mut controls.octaves, 1..=12).text("octaves"));
+                    ui.add(Slider::new(&mut controls.lacunarity, 0.1..=4.0).text("lacunarity"));
+                    ui.add(Slider::new(&mut controls.persistence, 0.0..=1.0).text("persistence"));
+                }
+                ui.add(Slider::new(&mut controls.frequency, 0.1..=8.0).text("frequency"));
+                if controls.noise_kind == NoiseKind::Worley {
+                    ui.add(
+                        Slider::new(&mut controls.worley_cell_size, 0.1..=8.0)
+                            .text("worley cell size"),
+                    );
+                }
+
+                ui.separator();
+                ui.add(Slider::new(&mut controls.iteration_speed, 1..=32).text("iteration speed"));
+                ui.add(Slider::new(&mut controls.trail_fade, 0.0..=4.0).text("trail fade"));
+                ui.add(
+                    Slider::new(&mut controls.particle_count, 1_000..=500_000)
+                        .text("particle count"),
+                );
+
+                let after = (
+                    controls.noise_kind,
+                    controls.seed,
+                    controls.octaves,
+                    controls.frequency,
+                    controls.lacunarity,
+                    controls.persistence,
+                    controls.worley_cell_size,
+                );
+                controls.noise_dirty = before != after;
+            });
+        });
+
+        self.update_textures(&full_output.textures_delta);
+        self.paint(full_output, buffer, width, height);
+    }
+
+    fn gather_input(&mut self, window: &Window, width: usize, height: usize) -> RawInput {
+        let mouse_pos = window
+            .get_mouse_pos(MouseMode::Clamp)
+            .map(|(x, y)| Pos2::new(x, y));
+
+        let mouse_down = window.get_mouse_down(MouseButton::Left);
+
+        let mut events = Vec::new();
+        if let Some(pos) = mouse_pos {
+            events.push(egui::Event::PointerMoved(pos));
+            if mouse_down != self.mouse_was_down {
+                events.push(egui::Event::PointerButton {
+                    pos,
+                    button: egui::PointerButton::Primary,
+                    pressed: mouse_down,
+                    modifiers: egui::Modifiers::default(),
+                });
+            }
+        }
+        self.mouse_was_down = mouse_down;
+
+        RawInput {
+            screen_rect: Some(Rect::from_min_size(
+                Pos2::ZERO,
+                egui::vec2(width as f32, height as f32),
+            )),
+            time: Some(self.start.elapsed().as_secs_f64()),
+            events,
+            ..Default::default()
+        }
+    }
+
+    /// Applies `delta` (new/updated/freed textures, chiefly the font atlas)
+    /// to [`Self::textures`] so [`Self::paint`] has something to sample.
+    fn update_textures(&mut self, delta: &TexturesDelta) {
+        for (id, image_delta) in &delta.set {
+            let ImageData::Color(image) = &image_delta.image;
+            match image_delta.pos {
+                None => {
+                    self.textures.insert(
+                        *id,
+                        Texture {
+                            size: image.size,
+                            pixels: image.pixels.clone(),
+                        },
+                    );
+                }
+                Some([x, y]) => {
+                    let Some(texture) = self.textures.get_mut(id) else {
+                        continue;
+                    };
+                    let [patch_w, patch_h] = image.size;
+                    let [tex_w, _] = texture.size;
+                    for row in 0..patch_h {
+                        let src = &image.pixels[row * patch_w..(row + 1) * patch_w];
+                        let dst_start = (y + row) * tex_w + x;
+                        texture.pixels[dst_start..dst_start + patch_w].copy_from_slice(src);
+                    }
+                }
+            }
+        }
+        for id in &delta.free {
+            self.textures.remove(id);
+        }
+    }
+
+    /// Rasterizes egui's tessellated triangles into the `u32` pixel buffer,
+    /// sampling whichever texture (the font atlas, for text/widget outlines)
+    /// each mesh references so labels and sliders stay legible instead of
+    /// collapsing into a flat average color.
+    fn paint(&self, full_output: FullOutput, buffer: &mut [u32], width: usize, height: usize) {
+        let clipped_primitives = self
+            .ctx
+            .tessellate(full_output.shapes, full_output.pixels_per_point);
+
+        for primitive in clipped_primitives {
+            let Primitive::Mesh(mesh) = primitive.primitive else {
+                continue;
+            };
+            let Some(texture) = self.textures.get(&mesh.texture_id) else {
+                continue;
+            };
+
+            let clip = primitive.clip_rect;
+            for tri in mesh.indices.chunks_exact(3) {
+                let [v0, v1, v2] = [
+                    mesh.vertices[tri[0] as usize],
+                    mesh.vertices[tri[1] as usize],
+                    mesh.vertices[tri[2] as usize],
+                ];
+                rasterize_triangle(v0, v1, v2, clip, texture, buffer, width, height);
+            }
+        }
+    }
+}
+
+/// Fills the (clipped) pixels covered by one triangle, barycentrically
+/// interpolating UV and vertex color so textured glyphs/widget fills render
+/// instead of a flat per-primitive color.
+#[allow(clippy::too_many_arguments)]
+fn rasterize_triangle(
+    v0: Vertex,
+    v1: Vertex,
+    v2: Vertex,
+    clip: Rect,
+    texture: &Texture,
+    buffer: &mut [u32],
+    width: usize,
+    height: usize,
+) {
+    let area = edge(v0.pos, v1.pos, v2.pos);
+    if area.abs() < f32::EPSILON {
+        return;
+    }
+
+    let min_x = v0.pos.x.min(v1.pos.x).min(v2.pos.x).max(clip.min.x).max(0.0);
+    let min_y = v0.pos.y.min(v1.pos.y).min(v2.pos.y).max(clip.min.y).max(0.0);
+    let max_x = v0.pos.x.max(v1.pos.x).max(v2.pos.x).min(clip.max.x).min(width as f32);
+    let max_y = v0.pos.y.max(v1.pos.y).max(v2.pos.y).min(clip.max.y).min(height as f32);
+    if min_x >= max_x || min_y >= max_y {
+        return;
+    }
+
+    let [tex_w, tex_h] = texture.size;
+
+    for y in (min_y as usize)..(max_y.ceil() as usize).min(height) {
+        for x in (min_x as usize)..(max_x.ceil() as usize).min(width) {
+            let p = Pos2::new(x as f32 + 0.5, y as f32 + 0.5);
+            let w0 = edge(v1.pos, v2.pos, p);
+            let w1 = edge(v2.pos, v0.pos, p);
+            let w2 = edge(v0.pos, v1.pos, p);
+            let inside = (w0 >= 0.0 && w1 >= 0.0 && w2 >= 0.0) || (w0 <= 0.0 && w1 <= 0.0 && w2 <= 0.0);
+            if !inside {
+                continue;
+            }
+            let (b0, b1, b2) = (w0 / area, w1 / area, w2 / area);
+
+            let u = b0 * v0.uv.x + b1 * v1.uv.x + b2 * v2.uv.x;
+            let v = b0 * v0.uv.y + b1 * v1.uv.y + b2 * v2.uv.y;
+            let tex_x = ((u * tex_w as f32) as usize).min(tex_w.saturating_sub(1));
+            let tex_y = ((v * tex_h as f32) as usize).min(tex_h.saturating_sub(1));
+            let texel = texture.pixels[tex_y * tex_w + tex_x];
+
+            // Vertex color is per-vertex, not per-texel, so take the nearest
+            // vertex's rather than interpolating three more channels per pixel.
+            let vertex_color = if b0 >= b1 && b0 >= b2 {
+                v0.color
+            } else if b1 >= b2 {
+                v1.color
+            } else {
+                v2.color
+            };
+
+            let src = modulate(texel, vertex_color);
+            if src.a() == 0 {
+                continue;
+            }
+
+            let idx = y * width + x;
+            buffer[idx] = blend_over(buffer[idx], src);
+        }
+    }
+}
+
+fn edge(a: Pos2, b: Pos2, c: Pos2) -> f32 {
+    (b.x - a.x) * (c.y - a.y) - (b.y - a.y) * (c.x - a.x)
+}
+
+/// Multiplies two premultiplied-alpha colors channel-wise, the way egui's
+/// own backends tint a texture sample (the font atlas) by the vertex color.
+fn modulate(texel: Color32, vertex: Color32) -> Color32 {
+    let mul = |a: u8, b: u8| ((a as u32 * b as u32) / 255) as u8;
+    Color32::from_rgba_premultiplied(
+        mul(texel.r(), vertex.r()),
+        mul(texel.g(), vertex.g()),
+        mul(texel.b(), vertex.b()),
+        mul(texel.a(), vertex.a()),
+    )
+}
+
+/// Premultiplied-alpha "over" blend of `src` onto the opaque `0x00RRGGBB` `dst`.
+fn blend_over(dst: u32, src: Color32) -> u32 {
+    let dst_r = (dst >> 16) & 0xff;
+    let dst_g = (dst >> 8) & 0xff;
+    let dst_b = dst & 0xff;
+
+    let inv_a = 255 - src.a() as u32;
+    let r = src.r() as u32 + (dst_r * inv_a) / 255;
+    let g = src.g() as u32 + (dst_g * inv_a) / 255;
+    let b = src.b() as u32 + (dst_b * inv_a) / 255;
+
+    (r.min(255) << 16) | (g.min(255) << 8) | b.min(255)
+}